@@ -0,0 +1,276 @@
+use std::{error::Error, io, sync::mpsc, thread, time::Duration};
+
+use signal_hook::{
+    consts::{SIGCONT, SIGINT, SIGTERM, SIGTSTP},
+    iterator::Signals,
+};
+use tui::Terminal;
+
+// how often a `Tick` is emitted to refresh the frame
+const TICK_RATE: Duration = Duration::from_millis(100);
+
+// a backend-neutral key, so the event loops never name `termion`/`crossterm` types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Enter,
+    Esc,
+    Space,
+    Char(char),
+    Other,
+}
+
+// the process-level events we care about, normalized away from raw signal numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimrSignal {
+    // SIGINT/SIGTERM — tear down and exit
+    Interrupt,
+    // SIGTSTP (Ctrl-Z) — restore the terminal and stop
+    Suspend,
+    // SIGCONT — re-enter raw mode and carry on
+    Continue,
+}
+
+pub enum TimrEvent {
+    Input(Key),
+    Tick,
+    Signal(TimrSignal),
+}
+
+// only the input (and signal) source is drained — the caller handles keys itself
+pub fn key_events() -> mpsc::Receiver<TimrEvent> {
+    let (tx, rx) = mpsc::channel();
+    spawn_input(tx.clone());
+    spawn_signals(tx);
+    rx
+}
+
+// input, signals, and a steady `Tick`, all feeding the same channel
+pub fn all_events() -> mpsc::Receiver<TimrEvent> {
+    let (tx, rx) = mpsc::channel();
+    spawn_input(tx.clone());
+    spawn_signals(tx.clone());
+
+    thread::spawn(move || loop {
+        if let Err(err) = tx.send(TimrEvent::Tick) {
+            eprintln!("{}", err);
+            return;
+        }
+        thread::sleep(TICK_RATE);
+    });
+
+    rx
+}
+
+// feed SIGINT/SIGTERM/SIGTSTP/SIGCONT into the same channel as keys and ticks
+fn spawn_signals(tx: mpsc::Sender<TimrEvent>) {
+    let mut signals = match Signals::new([SIGINT, SIGTERM, SIGTSTP, SIGCONT]) {
+        Ok(signals) => signals,
+        Err(err) => {
+            eprintln!("{}", err);
+            return;
+        }
+    };
+
+    thread::spawn(move || {
+        for signal in &mut signals {
+            let event = match signal {
+                SIGINT | SIGTERM => TimrSignal::Interrupt,
+                SIGTSTP => TimrSignal::Suspend,
+                SIGCONT => TimrSignal::Continue,
+                _ => continue,
+            };
+            if tx.send(TimrEvent::Signal(event)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+// restores the terminal when dropped, so a panic never leaves it corrupted
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TerminalGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+#[cfg(not(feature = "crossterm"))]
+mod imp {
+    use super::*;
+    use std::cell::RefCell;
+    use std::io::{Stdout, Write};
+    use termion::{
+        event::Key as TermionKey,
+        input::TermRead,
+        raw::{IntoRawMode, RawTerminal},
+        screen::AlternateScreen,
+    };
+    use tui::backend::TermionBackend;
+
+    // raw mode is a tty attribute, not a property of the writer, so we hold the
+    // `RawTerminal` separately from the backend purely to toggle it around a suspend
+    thread_local! {
+        static RAW: RefCell<Option<RawTerminal<Stdout>>> = const { RefCell::new(None) };
+    }
+
+    pub type TimrTerminal = Terminal<TermionBackend<AlternateScreen<Stdout>>>;
+
+    pub fn setup_terminal() -> Result<TimrTerminal, Box<dyn Error>> {
+        let raw = io::stdout().into_raw_mode()?;
+        RAW.with(|cell| *cell.borrow_mut() = Some(raw));
+        let screen = AlternateScreen::from(io::stdout());
+        let backend = TermionBackend::new(screen);
+        Ok(Terminal::new(backend)?)
+    }
+
+    fn set_raw_mode(enabled: bool) -> Result<(), Box<dyn Error>> {
+        RAW.with(|cell| {
+            if let Some(raw) = cell.borrow().as_ref() {
+                if enabled {
+                    raw.activate_raw_mode()?;
+                } else {
+                    raw.suspend_raw_mode()?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn leave_screen() -> Result<(), Box<dyn Error>> {
+        let mut stdout = io::stdout();
+        write!(stdout, "{}{}", termion::screen::ToMainScreen, termion::cursor::Show)?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    // disable raw mode and drop back to the main screen, so the guard leaves the tty
+    // usable even on a panic
+    pub fn restore() -> Result<(), Box<dyn Error>> {
+        set_raw_mode(false)?;
+        leave_screen()
+    }
+
+    // before stopping, leave the alternate screen *and* cooked the tty so the shell we
+    // are backgrounded to has echo and line editing
+    pub fn suspend() -> Result<(), Box<dyn Error>> {
+        restore()
+    }
+
+    pub fn resume() -> Result<(), Box<dyn Error>> {
+        let mut stdout = io::stdout();
+        write!(
+            stdout,
+            "{}{}",
+            termion::screen::ToAlternateScreen,
+            termion::cursor::Hide
+        )?;
+        stdout.flush()?;
+        set_raw_mode(true)
+    }
+
+    fn map_key(key: TermionKey) -> Key {
+        match key {
+            TermionKey::Char('\n') => Key::Enter,
+            TermionKey::Char(' ') => Key::Space,
+            TermionKey::Esc => Key::Esc,
+            TermionKey::Char(c) => Key::Char(c),
+            _ => Key::Other,
+        }
+    }
+
+    pub(super) fn spawn_input(tx: mpsc::Sender<TimrEvent>) {
+        thread::spawn(move || {
+            let stdin = io::stdin();
+            for key in stdin.keys().flatten() {
+                if let Err(err) = tx.send(TimrEvent::Input(map_key(key))) {
+                    eprintln!("{}", err);
+                    return;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(feature = "crossterm")]
+mod imp {
+    use super::*;
+    use std::io::Stdout;
+    use crossterm::{
+        cursor::{Hide, Show},
+        event::{self, Event, KeyCode},
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    };
+    use tui::backend::CrosstermBackend;
+
+    pub type TimrTerminal = Terminal<CrosstermBackend<Stdout>>;
+
+    pub fn setup_terminal() -> Result<TimrTerminal, Box<dyn Error>> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, Hide)?;
+        let backend = CrosstermBackend::new(stdout);
+        Ok(Terminal::new(backend)?)
+    }
+
+    pub fn restore() -> Result<(), Box<dyn Error>> {
+        disable_raw_mode()?;
+        execute!(io::stdout(), LeaveAlternateScreen, Show)?;
+        Ok(())
+    }
+
+    // stopping uses the same teardown as a clean exit
+    pub fn suspend() -> Result<(), Box<dyn Error>> {
+        restore()
+    }
+
+    pub fn resume() -> Result<(), Box<dyn Error>> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(())
+    }
+
+    fn map_key(code: KeyCode) -> Key {
+        match code {
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Char(' ') => Key::Space,
+            KeyCode::Char(c) => Key::Char(c),
+            _ => Key::Other,
+        }
+    }
+
+    pub(super) fn spawn_input(tx: mpsc::Sender<TimrEvent>) {
+        thread::spawn(move || loop {
+            match event::read() {
+                Ok(Event::Key(key)) => {
+                    if let Err(err) = tx.send(TimrEvent::Input(map_key(key.code))) {
+                        eprintln!("{}", err);
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return;
+                }
+            }
+        });
+    }
+}
+
+use imp::spawn_input;
+pub use imp::{restore, resume, setup_terminal, suspend, TimrTerminal};
@@ -1,28 +1,80 @@
+mod backend;
+mod theme;
+
 use clap::Parser;
+use notify_rust::Notification;
 use std::{
     error::Error,
-    io::{self, Stdout},
-    sync::{
-        atomic::{AtomicUsize, Ordering},
-        mpsc, Arc,
-    },
-    thread,
-    time::Duration,
-};
-use termion::{
-    event::Key,
-    input::TermRead,
-    raw::{IntoRawMode, RawTerminal},
-    screen::AlternateScreen,
+    io::{self, Write},
+    time::{Duration, Instant},
 };
 use tui::{
-    backend::{Backend, TermionBackend},
+    backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::Style,
-    text::Span,
+    style::{Modifier, Style},
+    text::{Span, Spans, Text},
     widgets::Paragraph,
-    Frame, Terminal,
+    Frame,
+};
+
+use crate::backend::{
+    all_events, key_events, resume, setup_terminal, suspend, Key, TerminalGuard, TimrEvent,
+    TimrSignal, TimrTerminal,
 };
+use crate::theme::{parse_color, Theme};
+
+// the countdown turns to the warning color once this little time is left
+const WARN_THRESHOLD: Duration = Duration::from_secs(5);
+
+// height of a single seven-segment glyph, in rows
+const GLYPH_HEIGHT: usize = 5;
+// a single blank column rendered between adjacent glyphs
+const GLYPH_SEP: &str = " ";
+
+// a fixed-height block of `█`/space rows for every character the clock can show
+fn glyph(c: char) -> [&'static str; GLYPH_HEIGHT] {
+    match c {
+        '0' => ["█████", "█   █", "█   █", "█   █", "█████"],
+        '1' => ["    █", "    █", "    █", "    █", "    █"],
+        '2' => ["█████", "    █", "█████", "█    ", "█████"],
+        '3' => ["█████", "    █", "█████", "    █", "█████"],
+        '4' => ["█   █", "█   █", "█████", "    █", "    █"],
+        '5' => ["█████", "█    ", "█████", "    █", "█████"],
+        '6' => ["█████", "█    ", "█████", "█   █", "█████"],
+        '7' => ["█████", "    █", "    █", "    █", "    █"],
+        '8' => ["█████", "█   █", "█████", "█   █", "█████"],
+        '9' => ["█████", "█   █", "█████", "    █", "█████"],
+        ':' => ["   ", " █ ", "   ", " █ ", "   "],
+        _ => ["     ", "     ", "     ", "     ", "     "],
+    }
+}
+
+// render `text` as a block of `GLYPH_HEIGHT` strings, concatenating each glyph row
+fn seven_segment(text: &str) -> Vec<String> {
+    let mut rows = vec![String::new(); GLYPH_HEIGHT];
+    for (i, c) in text.chars().enumerate() {
+        let g = glyph(c);
+        for (row, line) in rows.iter_mut().enumerate() {
+            if i > 0 {
+                line.push_str(GLYPH_SEP);
+            }
+            line.push_str(g[row]);
+        }
+    }
+    rows
+}
+
+// format a duration as `HH:MM:SS` once it reaches an hour, otherwise `MM:SS`
+fn format_remaining(remaining: Duration) -> String {
+    // round up so each value is shown for its full second (a 10s timer opens on 00:10)
+    let secs = (remaining.as_millis().div_ceil(1000)) as u64;
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{:02}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
 
 #[derive(Parser, Debug, Clone)]
 #[clap(version, about, long_about= None)]
@@ -30,45 +82,171 @@ pub struct Args {
     // the duration of the timer in seconds
     #[clap(short = 'd', long, default_value_t = 10)]
     duration: usize,
+
+    // color preset to paint the timer with (default, mono, high-contrast)
+    #[clap(short = 't', long, default_value = "default")]
+    theme: String,
+
+    // override the theme's primary color (name or `r,g,b`)
+    #[clap(short = 'c', long)]
+    color: Option<String>,
+
+    // an optional name for the timer, used in the completion notification
+    #[clap(short = 'l', long)]
+    label: Option<String>,
+
+    // ring the terminal bell (and flash the screen) when the timer finishes
+    #[clap(short = 'b', long)]
+    bell: bool,
+
+    // fire a desktop notification when the timer finishes
+    #[clap(short = 'n', long)]
+    notify: bool,
 }
 
+// how much time `+`/`-` add or remove from a running timer
+const ADJUST_STEP: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone)]
 struct App {
-    duration: Arc<AtomicUsize>,
+    // the working length of the timer; `+`/`-` grow and shrink it while running
+    duration: Duration,
+    // the length `r` resets back to, captured from the CLI
+    original: Duration,
+    // the instant the countdown was (re)started; `remaining` is derived from this
+    start: Instant,
+    // total time spent in completed pauses, subtracted from the raw elapsed
+    paused_accum: Duration,
+    // the instant the current pause began, if any
+    paused_at: Option<Instant>,
+    // the instant the countdown reached zero, while the alert screen is showing
+    finished_at: Option<Instant>,
+    // colors the timer is painted with
+    theme: Theme,
+    // completion alert configuration
+    label: String,
+    bell: bool,
+    notify: bool,
 }
 
 impl App {
-    fn new(args: Args) -> Self {
-        Self {
-            duration: Arc::new(AtomicUsize::from(args.duration)),
+    fn new(args: Args) -> Result<Self, Box<dyn Error>> {
+        let duration = Duration::from_secs(args.duration as u64);
+        let mut theme = args.theme.parse::<Theme>()?;
+        if let Some(color) = &args.color {
+            theme.primary = parse_color(color)?;
         }
+        Ok(Self {
+            duration,
+            original: duration,
+            start: Instant::now(),
+            paused_accum: Duration::ZERO,
+            paused_at: None,
+            finished_at: None,
+            theme,
+            label: args.label.unwrap_or_else(|| "Timer".to_string()),
+            bell: args.bell,
+            notify: args.notify,
+        })
+    }
+
+    // elapsed running time, with all paused intervals (completed and in-flight) removed
+    fn elapsed(&self) -> Duration {
+        let paused = self.paused_accum + self.paused_at.map(|at| at.elapsed()).unwrap_or_default();
+        self.start.elapsed().saturating_sub(paused)
+    }
+
+    // how much wall-clock time is left, derived from `start` so it never drifts
+    fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed())
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    // fire the configured alerts once and switch to the "Time's up!" screen
+    fn complete(&mut self) -> Result<(), Box<dyn Error>> {
+        self.finished_at = Some(Instant::now());
+        if self.bell {
+            print!("\x07");
+            io::stdout().flush()?;
+        }
+        if self.notify {
+            // a missing session bus (e.g. over SSH) must not crash the finished timer
+            let _ = Notification::new()
+                .summary("timr")
+                .body(&format!("{} finished", self.label))
+                .show();
+        }
+        Ok(())
+    }
+
+    // toggle pause/resume, folding a finished pause into `paused_accum` so the time
+    // spent paused is never retroactively counted against the countdown
+    fn toggle_pause(&mut self) {
+        match self.paused_at.take() {
+            Some(at) => self.paused_accum += at.elapsed(),
+            None => self.paused_at = Some(Instant::now()),
+        }
+    }
+
+    // restart the countdown from the original CLI duration
+    fn reset(&mut self) {
+        self.duration = self.original;
+        self.start = Instant::now();
+        self.paused_accum = Duration::ZERO;
+        self.paused_at = None;
+        self.finished_at = None;
+    }
+
+    fn add_time(&mut self) {
+        self.duration += ADJUST_STEP;
+    }
+
+    fn sub_time(&mut self) {
+        self.duration = self.duration.saturating_sub(ADJUST_STEP);
     }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
 
-    let stdout = io::stdout().into_raw_mode()?;
-    let stdout = AlternateScreen::from(stdout);
-    let backend = TermionBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    let mut app = App::new(args);
+    let mut terminal = setup_terminal()?;
+    // restores the terminal on the way out, including on a panic
+    let _guard = TerminalGuard::new();
+    let mut app = App::new(args)?;
 
     key_events_loop(&mut terminal, &mut app).unwrap();
     Ok(())
 }
 
-fn key_events_loop(
-    mut terminal: &mut Terminal<TermionBackend<AlternateScreen<RawTerminal<Stdout>>>>,
-    mut app: &mut App,
-) -> Result<(), Box<dyn Error>> {
+// restore the terminal, stop the process so the shell can background it, then
+// re-enter raw mode once we are resumed (SIGCONT)
+fn handle_suspend() -> Result<(), Box<dyn Error>> {
+    suspend()?;
+    // `emulate_default_handler` performs the actual stop by raising SIGSTOP, leaving our
+    // handlers in place; re-raising SIGTSTP would just loop back into our handler.
+    // It returns once we are resumed (SIGCONT), so re-enter raw mode and the alternate
+    // screen here, before the loop's next draw paints onto the shell's main screen.
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+    resume()?;
+    Ok(())
+}
+
+fn key_events_loop(terminal: &mut TimrTerminal, app: &mut App) -> Result<(), Box<dyn Error>> {
     let events = key_events();
     loop {
-        terminal.draw(|f| draw(f, &mut app).unwrap()).unwrap();
+        terminal.draw(|f| draw(f, app).unwrap()).unwrap();
         match events.recv()? {
             TimrEvent::Input(key) => match key {
-                Key::Char('\n') => {
-                    all_events_loop(&mut terminal, &mut app).unwrap();
+                Key::Enter => {
+                    app.start = Instant::now();
+                    all_events_loop(terminal, app).unwrap();
                     return Ok(());
                 }
                 Key::Esc => {
@@ -76,107 +254,163 @@ fn key_events_loop(
                 }
                 _ => {}
             },
-            _ => {}
+            TimrEvent::Signal(signal) => match signal {
+                TimrSignal::Interrupt => return Ok(()),
+                TimrSignal::Suspend => handle_suspend()?,
+                TimrSignal::Continue => resume()?,
+            },
+            TimrEvent::Tick => {}
         }
     }
 }
 
-fn all_events_loop(
-    terminal: &mut Terminal<TermionBackend<AlternateScreen<RawTerminal<Stdout>>>>,
-    mut app: &mut App,
-) -> Result<(), Box<dyn Error>> {
+fn all_events_loop(terminal: &mut TimrTerminal, app: &mut App) -> Result<(), Box<dyn Error>> {
     let events = all_events();
     loop {
-        terminal.draw(|f| draw(f, &mut app).unwrap()).unwrap();
+        terminal.draw(|f| draw(f, app).unwrap()).unwrap();
         match events.recv()? {
             TimrEvent::Tick => {
-                if app.duration.load(Ordering::SeqCst) == 0 {
-                    break;
+                // derive completion from real elapsed time; the tick only refreshes the
+                // frame and never advances while paused
+                if !app.is_finished() && !app.is_paused() && app.elapsed() >= app.duration {
+                    app.complete()?;
                 }
-                app.duration.fetch_sub(1, Ordering::Relaxed); //atomic version of -=1 for a counter
             }
+            // once finished, the alert screen lingers until any key dismisses it
+            TimrEvent::Input(_) if app.is_finished() => return Ok(()),
             TimrEvent::Input(key) => match key {
                 Key::Esc => {
                     return Ok(());
                 }
+                Key::Space => app.toggle_pause(),
+                Key::Char('r') => app.reset(),
+                Key::Char('+') => app.add_time(),
+                Key::Char('-') => app.sub_time(),
                 _ => {}
             },
+            TimrEvent::Signal(signal) => match signal {
+                TimrSignal::Interrupt => return Ok(()),
+                TimrSignal::Suspend => handle_suspend()?,
+                TimrSignal::Continue => resume()?,
+            },
         }
     }
-    return Ok(());
-}
-
-enum TimrEvent {
-    Input(Key),
-    Tick,
 }
 
-fn all_events() -> mpsc::Receiver<TimrEvent> {
-    let (tx, rx) = mpsc::channel();
-    let key_tx = tx.clone();
-
-    thread::spawn(move || {
-        let stdin = io::stdin();
-        for key in stdin.keys().flatten() {
-            if let Err(err) = key_tx.send(TimrEvent::Input(key)) {
-                eprintln!("{}", err);
-                return;
-            }
-        }
-    });
-
-    thread::spawn(move || loop {
-        if let Err(err) = tx.send(TimrEvent::Tick) {
-            eprintln!("{}", err);
-            return;
-        }
-        thread::sleep(Duration::from_millis(100));
-    });
-
-    rx
-}
-
-fn key_events() -> mpsc::Receiver<TimrEvent> {
-    let (tx, rx) = mpsc::channel();
-
-    thread::spawn(move || {
-        let stdin = io::stdin();
-        for key in stdin.keys().flatten() {
-            if let Err(err) = tx.send(TimrEvent::Input(key)) {
-                eprintln!("{}", err);
-                return;
-            }
-        }
-    });
-
-    rx
-}
-
-fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) -> Result<(), ()> {
-    let h = &f.size().height;
-    let height_of_timer = 1.;
-    let mar = ((*h as f64 - height_of_timer) / 2.) as u16;
+// vertically center a `height`-row block of text against the frame and render it
+fn render_centered<B: Backend>(f: &mut Frame<B>, text: Text, height: u16) {
+    let size = f.size();
+    let mar = size.height.saturating_sub(height) / 2;
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
             [
-                Constraint::Length(mar as u16),
-                Constraint::Length(height_of_timer as u16),
-                Constraint::Length(mar as u16),
+                Constraint::Length(mar),
+                Constraint::Length(height),
+                Constraint::Length(mar),
             ]
             .as_ref(),
         )
-        .split(f.size());
+        .split(size);
 
     f.render_widget(
-        Paragraph::new(Span::styled(
-            String::from(format!("{:?}", app.duration)),
-            Style::default(),
-        ))
-        .alignment(Alignment::Center),
+        Paragraph::new(text).alignment(Alignment::Center),
         chunks[1],
     );
+}
+
+fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) -> Result<(), ()> {
+    // the timer has finished: hold a flashing "Time's up!" screen until dismissed
+    if let Some(since) = app.finished_at {
+        let mut style = Style::default()
+            .fg(app.theme.warning)
+            .bg(app.theme.background);
+        // invert the style a few times a second while `--bell` is set, for a visible flash
+        if app.bell && (since.elapsed().as_millis() / 300) % 2 == 0 {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        render_centered(f, Text::from(Span::styled("TIME'S UP!", style)), 1);
+        return Ok(());
+    }
+
+    let size = f.size();
+    let remaining = app.remaining();
+    let label = format_remaining(remaining);
+    let block = seven_segment(&label);
+
+    // flip to the warning color for the final stretch so it reads at a glance
+    let fg = if remaining <= WARN_THRESHOLD {
+        app.theme.warning
+    } else {
+        app.theme.primary
+    };
+    let style = Style::default().fg(fg).bg(app.theme.background);
+
+    // the block width is set by the widest row; fall back to the plain single-line
+    // form whenever the terminal cannot comfortably fit the big digits
+    let block_w = block.iter().map(|row| row.chars().count()).max().unwrap_or(0) as u16;
+    let fits = size.height as usize >= GLYPH_HEIGHT && size.width >= block_w;
+
+    let (mut text, mut height_of_timer) = if fits {
+        let lines = block
+            .into_iter()
+            .map(|row| Spans::from(Span::styled(row, style)))
+            .collect::<Vec<_>>();
+        (Text::from(lines), GLYPH_HEIGHT as u16)
+    } else {
+        (Text::from(Span::styled(label, style)), 1)
+    };
+
+    // surface the paused state on its own line so a stopped clock is unmistakable
+    if app.is_paused() {
+        text.extend(Text::from(Span::styled(
+            "PAUSED",
+            Style::default().fg(app.theme.secondary),
+        )));
+        height_of_timer += 1;
+    }
+
+    render_centered(f, text, height_of_timer);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_remaining_uses_mm_ss_under_an_hour() {
+        assert_eq!(format_remaining(Duration::from_secs(65)), "01:05");
+        assert_eq!(format_remaining(Duration::from_secs(9)), "00:09");
+    }
+
+    #[test]
+    fn format_remaining_switches_to_hh_mm_ss() {
+        assert_eq!(format_remaining(Duration::from_secs(3661)), "01:01:01");
+    }
+
+    #[test]
+    fn format_remaining_rounds_up_to_the_full_second() {
+        // a fresh 10s timer should read 00:10, not 00:09
+        assert_eq!(format_remaining(Duration::from_millis(10_000)), "00:10");
+        assert_eq!(format_remaining(Duration::from_millis(9_500)), "00:10");
+        assert_eq!(format_remaining(Duration::from_millis(9_000)), "00:09");
+        assert_eq!(format_remaining(Duration::ZERO), "00:00");
+    }
+
+    #[test]
+    fn seven_segment_block_has_fixed_height_and_aligned_rows() {
+        let block = seven_segment("12:34");
+        assert_eq!(block.len(), GLYPH_HEIGHT);
+        let width = block[0].chars().count();
+        assert!(block.iter().all(|row| row.chars().count() == width));
+    }
+
+    #[test]
+    fn seven_segment_renders_known_digit_rows() {
+        let block = seven_segment("1");
+        assert!(block.iter().zip(glyph('1')).all(|(row, glyph)| row == glyph));
+    }
+}
@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use tui::style::Color;
+
+// the handful of colors the timer paints with; threaded through `App` and applied
+// to the timer `Paragraph` in `draw`
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub primary: Color,
+    pub secondary: Color,
+    pub background: Color,
+    // used once the countdown enters its final few seconds
+    pub warning: Color,
+}
+
+impl Theme {
+    pub fn default_theme() -> Self {
+        Self {
+            primary: Color::Cyan,
+            secondary: Color::Gray,
+            background: Color::Reset,
+            warning: Color::Red,
+        }
+    }
+
+    pub fn mono() -> Self {
+        Self {
+            primary: Color::White,
+            secondary: Color::DarkGray,
+            background: Color::Reset,
+            warning: Color::White,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            primary: Color::Yellow,
+            secondary: Color::White,
+            background: Color::Black,
+            warning: Color::Red,
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(Self::default_theme()),
+            "mono" => Ok(Self::mono()),
+            "high-contrast" | "high_contrast" | "hc" => Ok(Self::high_contrast()),
+            other => Err(format!("unknown theme `{}`", other)),
+        }
+    }
+}
+
+// parse a named or `r,g,b` color for the `--color` override
+pub fn parse_color(s: &str) -> Result<Color, String> {
+    match s.to_lowercase().as_str() {
+        "black" => Ok(Color::Black),
+        "red" => Ok(Color::Red),
+        "green" => Ok(Color::Green),
+        "yellow" => Ok(Color::Yellow),
+        "blue" => Ok(Color::Blue),
+        "magenta" => Ok(Color::Magenta),
+        "cyan" => Ok(Color::Cyan),
+        "gray" | "grey" => Ok(Color::Gray),
+        "white" => Ok(Color::White),
+        _ => {
+            let parts = s.split(',').map(str::trim).collect::<Vec<_>>();
+            if let [r, g, b] = parts.as_slice() {
+                let r = r.parse().map_err(|_| format!("invalid color `{}`", s))?;
+                let g = g.parse().map_err(|_| format!("invalid color `{}`", s))?;
+                let b = b.parse().map_err(|_| format!("invalid color `{}`", s))?;
+                Ok(Color::Rgb(r, g, b))
+            } else {
+                Err(format!("unknown color `{}`", s))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_theme_names_parse() {
+        assert_eq!("default".parse::<Theme>().unwrap().primary, Color::Cyan);
+        assert_eq!("mono".parse::<Theme>().unwrap().primary, Color::White);
+        assert_eq!("hc".parse::<Theme>().unwrap().primary, Color::Yellow);
+    }
+
+    #[test]
+    fn unknown_theme_name_errors() {
+        assert!("sepia".parse::<Theme>().is_err());
+    }
+
+    #[test]
+    fn parse_color_accepts_names_and_rgb() {
+        assert_eq!(parse_color("red").unwrap(), Color::Red);
+        assert_eq!(parse_color("GREY").unwrap(), Color::Gray);
+        assert_eq!(parse_color("10, 20, 30").unwrap(), Color::Rgb(10, 20, 30));
+    }
+
+    #[test]
+    fn parse_color_rejects_bad_input() {
+        // unknown name, out-of-range channel, and wrong component count all error
+        assert!(parse_color("chartreuse").is_err());
+        assert!(parse_color("300,0,0").is_err());
+        assert!(parse_color("1,2").is_err());
+    }
+}